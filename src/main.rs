@@ -1,11 +1,111 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
 use git2::{IndexAddOption, Repository, StatusOptions};
 use anyhow::{bail, Context, Result};
-use lazy_static::lazy_static;
 use regex::Regex;
 use log::debug;
 
-lazy_static! {
-  static ref RE: Regex = Regex::new(r"^([A-Z]+-\d+)(\S*)?(?:\s+(.*))?$").unwrap();
+const DEFAULT_TICKET_PATTERN: &str = r"^([A-Z]+-\d+)(\S*)?(?:\s+(.*))?$";
+
+static TICKET_REGEX: OnceLock<Regex> = OnceLock::new();
+
+// Find a user-supplied ticket pattern, checked in order of specificity:
+// env var, then `commitment.ticketPattern` git config, then `commitment.toml`
+// in the repo's worktree. Falls back to the built-in JIRA-style pattern.
+fn resolve_ticket_pattern() -> String {
+  if let Ok(pattern) = std::env::var("COMMITMENT_TICKET_PATTERN") {
+    return pattern;
+  }
+
+  if let Ok(repo) = Repository::discover(".") {
+    if let Ok(config) = repo.config() {
+      if let Ok(pattern) = config.get_string("commitment.ticketPattern") {
+        return pattern;
+      }
+    }
+
+    if let Some(workdir) = repo.workdir() {
+      if let Ok(contents) = std::fs::read_to_string(workdir.join("commitment.toml")) {
+        if let Some(pattern) = ticket_pattern_from_toml(&contents) {
+          return pattern;
+        }
+      }
+    }
+  }
+
+  DEFAULT_TICKET_PATTERN.to_string()
+}
+
+// Extract `ticket_pattern = "..."` from a `commitment.toml`'s contents,
+// un-escaping it as a TOML basic string (`\\` -> `\`, `\"` -> `"`, ...).
+// `\d`/`\s`/`\w` in a ticket regex can only reach us TOML-escaped as `\\d`
+// etc, so a naive `\"`-only unescape leaves those backslashes doubled.
+fn ticket_pattern_from_toml(contents: &str) -> Option<String> {
+  let key_re = Regex::new(r#"(?m)^\s*ticket_pattern\s*=\s*"((?:[^"\\]|\\.)*)"\s*$"#).unwrap();
+  let cap = key_re.captures(contents)?;
+
+  Some(unescape_toml_basic_string(&cap[1]))
+}
+
+fn unescape_toml_basic_string(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  let mut chars = s.chars();
+
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      out.push(c);
+      continue;
+    }
+
+    match chars.next() {
+      Some('\\') => out.push('\\'),
+      Some('"') => out.push('"'),
+      Some('n') => out.push('\n'),
+      Some('t') => out.push('\t'),
+      Some('r') => out.push('\r'),
+      Some(other) => {
+        out.push('\\');
+        out.push(other);
+      }
+      None => out.push('\\')
+    }
+  }
+
+  out
+}
+
+// Compile a candidate ticket pattern, enforcing the three capture groups
+// (`to_ticket`/`create_commit` rely on: ticket key, discard suffix, message)
+fn compile_ticket_pattern(pattern: &str) -> Result<Regex> {
+  let regex = Regex::new(pattern).with_context(|| format!("Invalid `commitment.ticketPattern` {:?}", pattern))?;
+
+  if regex.captures_len() != 4 {
+    bail!(
+      "`commitment.ticketPattern` {:?} must have exactly 3 capture groups (ticket, discard suffix, message), found {}",
+      pattern,
+      regex.captures_len().saturating_sub(1)
+    );
+  }
+
+  Ok(regex)
+}
+
+// Resolve, validate and cache the ticket regex. Call this once, early in
+// `main`, so a misconfigured `commitment.ticketPattern` surfaces as a clean
+// error instead of panicking deep inside `to_ticket()`.
+fn init_ticket_pattern() -> Result<()> {
+  let regex = compile_ticket_pattern(&resolve_ticket_pattern())?;
+  let _ = TICKET_REGEX.set(regex);
+  Ok(())
+}
+
+// The active ticket regex, falling back to the default pattern if
+// `init_ticket_pattern` was never called (e.g. in tests)
+fn ticket_regex() -> &'static Regex {
+  TICKET_REGEX.get_or_init(|| compile_ticket_pattern(DEFAULT_TICKET_PATTERN).expect("default ticket pattern is valid"))
 }
 
 pub(crate) trait Ticket {
@@ -19,7 +119,7 @@ impl Ticket for str {
       return (None, None);
     }
 
-    if let Some(cap) = RE.captures(self) {
+    if let Some(cap) = ticket_regex().captures(self) {
       let ticket = cap.get(1).map(|m| m.as_str());
       let rest = cap.get(3).map(|m| m.as_str());
       return (ticket, rest);
@@ -62,21 +162,246 @@ fn has_repo_uncommited_changes(repo: &Repository) -> Result<bool> {
   }
 }
 
-pub fn add_and_commit(repo: &Repository, msg: &str) -> Result<()> {
+// Resolve the hooks directory, honoring `core.hooksPath` when set
+fn hooks_dir(repo: &Repository) -> Result<PathBuf> {
+  if let Ok(config) = repo.config() {
+    if let Ok(path) = config.get_string("core.hooksPath") {
+      return Ok(repo.workdir().unwrap_or_else(|| repo.path()).join(path));
+    }
+  }
+
+  Ok(repo.path().join("hooks"))
+}
+
+// Check whether a hook file exists and is executable
+fn is_executable_hook(path: &std::path::Path) -> bool {
+  if !path.is_file() {
+    return false;
+  }
+
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+  }
+
+  #[cfg(not(unix))]
+  {
+    true
+  }
+}
+
+// Run the `pre-commit` hook, if present, with the worktree as CWD
+fn run_pre_commit_hook(repo: &Repository, no_verify: bool) -> Result<()> {
+  if no_verify {
+    return Ok(());
+  }
+
+  let hook = hooks_dir(repo)?.join("pre-commit");
+  if !is_executable_hook(&hook) {
+    return Ok(());
+  }
+
+  let workdir = repo.workdir().context("Cannot run hooks in a bare repository")?;
+  let output = Command::new(&hook).current_dir(workdir).output().context("Failed to run `pre-commit` hook")?;
+
+  if !output.status.success() {
+    bail!("pre-commit hook failed:\n{}", String::from_utf8_lossy(&output.stderr));
+  }
+
+  Ok(())
+}
+
+// Run the `commit-msg` hook, if present, allowing it to rewrite the message
+fn run_commit_msg_hook(repo: &Repository, msg: &str, no_verify: bool) -> Result<String> {
+  if no_verify {
+    return Ok(msg.to_string());
+  }
+
+  let hook = hooks_dir(repo)?.join("commit-msg");
+  if !is_executable_hook(&hook) {
+    return Ok(msg.to_string());
+  }
+
+  let workdir = repo.workdir().context("Cannot run hooks in a bare repository")?;
+  let msg_file = repo.path().join("COMMIT_EDITMSG");
+  std::fs::write(&msg_file, msg).context("Failed to write COMMIT_EDITMSG")?;
+
+  let output = Command::new(&hook)
+    .arg(&msg_file)
+    .current_dir(workdir)
+    .output()
+    .context("Failed to run `commit-msg` hook")?;
+
+  if !output.status.success() {
+    bail!("commit-msg hook failed:\n{}", String::from_utf8_lossy(&output.stderr));
+  }
+
+  std::fs::read_to_string(&msg_file).context("Failed to read back COMMIT_EDITMSG")
+}
+
+// Like `repo.signature()`, but falls back to a synthetic name when
+// `user.name` is unset and `user.email` is configured, instead of failing
+fn signature_allow_undefined_name(repo: &Repository) -> Result<git2::Signature<'static>> {
+  match repo.signature() {
+    Ok(signature) => Ok(signature),
+    Err(e) if e.code() == git2::ErrorCode::NotFound => {
+      let config = repo.config().context("Failed to read git config")?;
+      let email = config.get_string("user.email").context("`user.email` is not set")?;
+
+      git2::Signature::now("unknown", &email).context("Failed to build synthetic signature")
+    }
+    Err(e) => Err(e.into()),
+  }
+}
+
+// Whether the commit should be signed: an explicit `--sign`/`--no-sign` flag
+// wins, otherwise fall back to the repo's `commit.gpgsign` config
+fn should_sign(repo: &Repository, sign: Option<bool>) -> bool {
+  if let Some(sign) = sign {
+    return sign;
+  }
+
+  repo.config().ok().and_then(|c| c.get_bool("commit.gpgsign").ok()).unwrap_or(false)
+}
+
+// Sign a commit buffer with the backend configured via `gpg.format`
+fn sign_buffer(repo: &Repository, buffer: &str) -> Result<String> {
+  let config = repo.config().context("Failed to read git config")?;
+  let format = config.get_string("gpg.format").unwrap_or_else(|_| "openpgp".to_string());
+  let key = config.get_string("user.signingkey").context("`user.signingkey` is not set")?;
+
+  match format.as_str() {
+    "ssh" => sign_with_ssh(&key, buffer),
+    _ => sign_with_gpg(&key, buffer),
+  }
+}
+
+fn run_signer(program: &str, args: &[&str], buffer: &str) -> Result<String> {
+  let mut child = Command::new(program)
+    .args(args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .with_context(|| format!("Failed to run `{}`; is it installed?", program))?;
+
+  child.stdin.take().context("Failed to open signer stdin")?.write_all(buffer.as_bytes())?;
+  let output = child.wait_with_output().with_context(|| format!("Failed to wait for `{}`", program))?;
+
+  if !output.status.success() {
+    bail!("{} signing failed:\n{}", program, String::from_utf8_lossy(&output.stderr));
+  }
+
+  String::from_utf8(output.stdout).with_context(|| format!("`{}` produced a non-UTF8 signature", program))
+}
+
+fn sign_with_gpg(key: &str, buffer: &str) -> Result<String> {
+  run_signer("gpg", &["--detach-sign", "--armor", "-u", key], buffer)
+}
+
+fn sign_with_ssh(key: &str, buffer: &str) -> Result<String> {
+  run_signer("ssh-keygen", &["-Y", "sign", "-f", key, "-n", "git"], buffer)
+}
+
+// Stage modified/deleted tracked files only, skipping untracked ones
+fn stage_tracked_only(repo: &Repository, index: &mut git2::Index, pathspecs: &[String]) -> Result<()> {
+  let mut options = StatusOptions::new();
+  options.include_untracked(false);
+  for spec in pathspecs {
+    options.pathspec(spec);
+  }
+
+  let statuses = repo.statuses(Some(&mut options)).context("Failed to get statuses")?;
+
+  for entry in statuses.iter() {
+    let Some(path) = entry.path() else { continue };
+    let status = entry.status();
+
+    if status.intersects(git2::Status::WT_DELETED | git2::Status::INDEX_DELETED) {
+      index.remove_path(std::path::Path::new(path)).with_context(|| format!("Failed to stage deletion of {}", path))?;
+    } else if status.intersects(git2::Status::WT_MODIFIED | git2::Status::WT_TYPECHANGE | git2::Status::WT_RENAMED) {
+      index.add_path(std::path::Path::new(path)).with_context(|| format!("Failed to stage {}", path))?;
+    }
+  }
+
+  Ok(())
+}
+
+pub fn add_and_commit(
+  repo: &Repository, msg: &str, no_verify: bool, sign: Option<bool>, amend: bool, pathspecs: &[String], tracked_only: bool
+) -> Result<()> {
   debug!("[commit] Committing with message");
 
   let mut index = repo.index().expect("Failed to get index");
 
-  index.add_all(["."], IndexAddOption::DEFAULT, None).context("Failed to run `git add`")?;
+  if tracked_only {
+    stage_tracked_only(repo, &mut index, pathspecs)?;
+  } else {
+    let specs: Vec<&str> = if pathspecs.is_empty() { vec!["."] } else { pathspecs.iter().map(String::as_str).collect() };
+    index.add_all(specs, IndexAddOption::DEFAULT, None).context("Failed to run `git add`")?;
+  }
   index.write().context("Failed to write index from `git add`")?;
 
+  run_pre_commit_hook(repo, no_verify)?;
+
   let oid = index.write_tree().context("Failed to write tree")?;
-  let signature = repo.signature().context("Failed to get signature")?;
+  let signature = signature_allow_undefined_name(repo)?;
   let tree = repo.find_tree(oid).context("Failed to find tree")?;
+
+  let msg = run_commit_msg_hook(repo, msg, no_verify)?;
+
+  if amend {
+    let head_commit =
+      repo.head().ok().and_then(|head| head.peel_to_commit().ok()).context("Cannot amend: HEAD has no commits")?;
+
+    if should_sign(repo, sign) {
+      let author = head_commit.author();
+      let parent_commits: Vec<git2::Commit> = head_commit.parents().collect();
+      let parents: Vec<&git2::Commit> = parent_commits.iter().collect();
+
+      let buffer = repo
+        .commit_create_buffer(&author, &signature, &msg, &tree, parents.as_slice())
+        .context("Failed to build commit buffer")?;
+      let buffer = std::str::from_utf8(&buffer).context("Commit buffer is not valid UTF-8")?;
+      let signature = sign_buffer(repo, buffer)?;
+      let oid = repo.commit_signed(buffer, &signature, Some("gpgsig")).context("Could not create signed commit")?;
+
+      let refname = repo
+        .find_reference("HEAD")
+        .ok()
+        .and_then(|head| head.symbolic_target().map(|s| s.to_string()))
+        .unwrap_or_else(|| "HEAD".to_string());
+      repo.reference(&refname, oid, true, "commit (amend, signed)").context("Failed to update HEAD")?;
+    } else {
+      head_commit
+        .amend(Some("HEAD"), None, None, None, Some(&msg), Some(&tree))
+        .context("Could not amend commit")?;
+    }
+
+    return Ok(());
+  }
+
   let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
   let parents = parent.iter().collect::<Vec<&git2::Commit>>();
 
-  repo.commit(Some("HEAD"), &signature, &signature, &msg, &tree, parents.as_slice()).context("Could not commit")?;
+  if should_sign(repo, sign) {
+    let buffer = repo
+      .commit_create_buffer(&signature, &signature, &msg, &tree, parents.as_slice())
+      .context("Failed to build commit buffer")?;
+    let buffer = std::str::from_utf8(&buffer).context("Commit buffer is not valid UTF-8")?;
+    let signature = sign_buffer(repo, buffer)?;
+    let oid = repo.commit_signed(buffer, &signature, Some("gpgsig")).context("Could not create signed commit")?;
+
+    let refname = repo
+      .find_reference("HEAD")
+      .ok()
+      .and_then(|head| head.symbolic_target().map(|s| s.to_string()))
+      .unwrap_or_else(|| "HEAD".to_string());
+    repo.reference(&refname, oid, true, "commit (signed)").context("Failed to update HEAD")?;
+  } else {
+    repo.commit(Some("HEAD"), &signature, &signature, &msg, &tree, parents.as_slice()).context("Could not commit")?;
+  }
 
   Ok(())
 }
@@ -92,21 +417,127 @@ fn get_branch_name(repo: &Repository) -> Result<String> {
   Ok(branch_name.to_string())
 }
 
+// Resolve the base branch to diff against: an explicit `--base` flag wins,
+// otherwise fall back to `commitment.base` git config, then "main"
+fn resolve_base_branch(repo: &Repository, base: Option<&str>) -> String {
+  if let Some(base) = base {
+    return base.to_string();
+  }
+
+  repo
+    .config()
+    .ok()
+    .and_then(|c| c.get_string("commitment.base").ok())
+    .unwrap_or_else(|| "main".to_string())
+}
+
+// Walk commits unique to HEAD (relative to the merge-base with `base`),
+// looking for a ticket key in either the branch names touching them or
+// their commit summaries. Used when the current branch name itself has
+// no recognizable ticket, e.g. on a detached HEAD or a descriptive name.
+fn find_ticket_from_base(repo: &Repository, base: &str) -> Option<String> {
+  let head_oid = repo.head().ok()?.target()?;
+  let base_oid = repo.revparse_single(base).ok()?.peel_to_commit().ok()?.id();
+  let merge_base = repo.merge_base(head_oid, base_oid).ok()?;
+
+  // Branches pointing at commits unique to HEAD, so a descriptively-named
+  // commit on a ticket-named branch (or vice versa) is still found
+  let branches_by_oid: Vec<(git2::Oid, String)> = repo
+    .branches(None)
+    .ok()?
+    .flatten()
+    .filter_map(|(branch, _)| {
+      let oid = branch.get().target()?;
+      let name = branch.name().ok()??.to_string();
+      Some((oid, name))
+    })
+    .collect();
+
+  let mut revwalk = repo.revwalk().ok()?;
+  revwalk.push(head_oid).ok()?;
+  revwalk.hide(merge_base).ok()?;
+
+  for oid in revwalk.flatten() {
+    if let Some((_, name)) = branches_by_oid.iter().find(|(branch_oid, _)| *branch_oid == oid) {
+      if let (Some(ticket), _) = name.as_str().to_ticket() {
+        return Some(ticket.to_string());
+      }
+    }
+
+    let commit = repo.find_commit(oid).ok()?;
+    if let Some(summary) = commit.summary() {
+      if let (Some(ticket), _) = summary.to_ticket() {
+        return Some(ticket.to_string());
+      }
+    }
+  }
+
+  None
+}
+
 fn main() -> Result<()> {
+  init_ticket_pattern()?;
+
   // Recursively search for a git repository
   let current_dir = std::env::current_dir()?;
   let flags = git2::RepositoryOpenFlags::empty();
   let repo = Repository::open_ext(current_dir, flags, &[] as &[&str])?;
 
-  if !has_repo_uncommited_changes(&repo)? {
+  let args = std::env::args().skip(1).collect::<Vec<String>>();
+  let (mut opt_args, pathspecs) = match args.iter().position(|a| a == "--") {
+    Some(i) => (args[..i].to_vec(), args[i + 1..].to_vec()),
+    None => (args, Vec::new()),
+  };
+
+  let base = match opt_args.iter().position(|a| a == "--base") {
+    Some(i) => {
+      opt_args.remove(i);
+      if i >= opt_args.len() {
+        bail!("--base requires a value");
+      }
+      Some(opt_args.remove(i))
+    }
+    None => None,
+  };
+
+  let no_verify = opt_args.iter().any(|a| a == "--no-verify");
+  let amend = opt_args.iter().any(|a| a == "--amend");
+  let tracked_only = opt_args.iter().any(|a| a == "--tracked-only");
+  let sign = if opt_args.iter().any(|a| a == "--sign") {
+    Some(true)
+  } else if opt_args.iter().any(|a| a == "--no-sign") {
+    Some(false)
+  } else {
+    None
+  };
+  let message = opt_args
+    .into_iter()
+    .filter(|a| !matches!(a.as_str(), "--no-verify" | "--sign" | "--no-sign" | "--amend" | "--tracked-only"))
+    .collect::<Vec<String>>()
+    .join(" ");
+
+  if !amend && !has_repo_uncommited_changes(&repo)? {
     bail!("No uncommitted changes found");
   }
 
-  let message = std::env::args().skip(1).collect::<Vec<String>>().join(" ");
+  let message = if amend && message.is_empty() {
+    let head_commit =
+      repo.head().ok().and_then(|head| head.peel_to_commit().ok()).context("Cannot amend: HEAD has no commits")?;
+    head_commit.summary().context("Existing commit message is not valid UTF-8")?.to_string()
+  } else {
+    message
+  };
+
   let branch_name = get_branch_name(&repo)?;
-  let msg = create_commit(branch_name.as_str(), &message)?;
+  let ticket_source = if branch_name.to_ticket().0.is_none() {
+    let base = resolve_base_branch(&repo, base.as_deref());
+    find_ticket_from_base(&repo, &base).unwrap_or_else(|| branch_name.clone())
+  } else {
+    branch_name
+  };
+  let msg = create_commit(ticket_source.as_str(), &message)?;
 
-  add_and_commit(&repo, &msg)?;
+  add_and_commit(&repo, &msg, no_verify, sign, amend, &pathspecs, tracked_only)?;
 
   Ok(())
 }
@@ -128,3 +559,304 @@ fn test_to_ticket() {
   assert_eq!("Head".to_ticket(), (None, Some("Head")));
   assert_eq!("".to_ticket(), (None, None));
 }
+
+// A throwaway git repository for tests that need real `Repository`
+// plumbing (hooks, signing, staging). Cleaned up on drop.
+#[cfg(test)]
+struct TempRepo {
+  dir:  PathBuf,
+  repo: Repository,
+}
+
+#[cfg(test)]
+impl Drop for TempRepo {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.dir);
+  }
+}
+
+#[cfg(test)]
+fn init_temp_repo() -> TempRepo {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+  let id = COUNTER.fetch_add(1, Ordering::SeqCst);
+  let dir = std::env::temp_dir().join(format!("commitment-test-{}-{}", std::process::id(), id));
+  std::fs::create_dir_all(&dir).expect("Failed to create temp dir");
+
+  let repo = Repository::init(&dir).expect("Failed to init temp repo");
+  let mut config = repo.config().expect("Failed to open temp repo config");
+  config.set_str("user.name", "Test User").unwrap();
+  config.set_str("user.email", "test@example.com").unwrap();
+
+  TempRepo { dir, repo }
+}
+
+#[cfg(test)]
+fn write_executable_hook(hooks_dir: &std::path::Path, name: &str, script: &str) {
+  std::fs::create_dir_all(hooks_dir).unwrap();
+  let path = hooks_dir.join(name);
+  std::fs::write(&path, script).unwrap();
+
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+  }
+}
+
+#[test]
+fn test_pre_commit_hook_sees_staged_changes() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("file.txt"), "content").unwrap();
+
+  write_executable_hook(
+    &tmp.repo.path().join("hooks"),
+    "pre-commit",
+    "#!/bin/sh\nset -e\n[ -n \"$(git diff --cached --name-only)\" ]\n"
+  );
+
+  assert!(add_and_commit(&tmp.repo, "Message", false, None, false, &[], false).is_ok());
+}
+
+#[test]
+fn test_no_verify_skips_failing_pre_commit_hook() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("file.txt"), "content").unwrap();
+
+  write_executable_hook(&tmp.repo.path().join("hooks"), "pre-commit", "#!/bin/sh\nexit 1\n");
+
+  assert!(add_and_commit(&tmp.repo, "Message", false, None, false, &[], false).is_err());
+  assert!(tmp.repo.head().is_err());
+
+  assert!(add_and_commit(&tmp.repo, "Message", true, None, false, &[], false).is_ok());
+  assert!(tmp.repo.head().is_ok());
+}
+
+#[test]
+fn test_should_sign_precedence() {
+  let tmp = init_temp_repo();
+
+  assert!(!should_sign(&tmp.repo, None));
+
+  let mut config = tmp.repo.config().unwrap();
+  config.set_bool("commit.gpgsign", true).unwrap();
+  assert!(should_sign(&tmp.repo, None));
+
+  assert!(!should_sign(&tmp.repo, Some(false)));
+  assert!(should_sign(&tmp.repo, Some(true)));
+}
+
+#[test]
+fn test_signature_allow_undefined_name_falls_back_to_unknown() {
+  let tmp = init_temp_repo();
+  let mut config = tmp.repo.config().unwrap();
+  config.remove("user.name").unwrap();
+
+  let signature = signature_allow_undefined_name(&tmp.repo).unwrap();
+  assert_eq!(signature.name(), Some("unknown"));
+  assert_eq!(signature.email(), Some("test@example.com"));
+}
+
+#[test]
+fn test_signature_allow_undefined_name_errors_without_email_either() {
+  let tmp = init_temp_repo();
+  let mut config = tmp.repo.config().unwrap();
+  config.remove("user.name").unwrap();
+  config.remove("user.email").unwrap();
+
+  assert!(signature_allow_undefined_name(&tmp.repo).is_err());
+}
+
+#[test]
+fn test_amend_replaces_previous_commit() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("file.txt"), "v1").unwrap();
+  add_and_commit(&tmp.repo, "First message", false, None, false, &[], false).unwrap();
+  let first_oid = tmp.repo.head().unwrap().target().unwrap();
+
+  std::fs::write(tmp.dir.join("file.txt"), "v2").unwrap();
+  add_and_commit(&tmp.repo, "Amended message", false, None, true, &[], false).unwrap();
+
+  let head_commit = tmp.repo.head().unwrap().peel_to_commit().unwrap();
+  assert_eq!(head_commit.summary(), Some("Amended message"));
+  assert_eq!(head_commit.parent_count(), 0);
+  assert_ne!(head_commit.id(), first_oid);
+}
+
+#[test]
+fn test_amend_fails_on_empty_repo() {
+  let tmp = init_temp_repo();
+  assert!(add_and_commit(&tmp.repo, "Message", false, None, true, &[], false).is_err());
+}
+
+#[test]
+fn test_compile_ticket_pattern_accepts_valid_pattern() {
+  let regex = compile_ticket_pattern(r"^(#\d+)(\S*)?(?:\s+(.*))?$").unwrap();
+  let cap = regex.captures("#42 Fix thing").unwrap();
+  assert_eq!(cap.get(1).unwrap().as_str(), "#42");
+  assert_eq!(cap.get(3).unwrap().as_str(), "Fix thing");
+}
+
+#[test]
+fn test_compile_ticket_pattern_rejects_invalid_regex() {
+  assert!(compile_ticket_pattern("(unclosed").is_err());
+}
+
+#[test]
+fn test_compile_ticket_pattern_rejects_wrong_capture_group_count() {
+  assert!(compile_ticket_pattern(r"^(#\d+)$").is_err());
+}
+
+#[test]
+fn test_pathspec_stages_only_matching_files() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("a.txt"), "a").unwrap();
+  std::fs::write(tmp.dir.join("b.txt"), "b").unwrap();
+
+  add_and_commit(&tmp.repo, "Only a", false, None, false, &["a.txt".to_string()], false).unwrap();
+
+  let head_commit = tmp.repo.head().unwrap().peel_to_commit().unwrap();
+  let tree = head_commit.tree().unwrap();
+  assert!(tree.get_path(std::path::Path::new("a.txt")).is_ok());
+  assert!(tree.get_path(std::path::Path::new("b.txt")).is_err());
+}
+
+#[test]
+fn test_tracked_only_skips_untracked_files() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("tracked.txt"), "v1").unwrap();
+  add_and_commit(&tmp.repo, "Initial", false, None, false, &[], false).unwrap();
+
+  std::fs::write(tmp.dir.join("tracked.txt"), "v2").unwrap();
+  std::fs::write(tmp.dir.join("untracked.txt"), "new").unwrap();
+
+  add_and_commit(&tmp.repo, "Tracked only", false, None, false, &[], true).unwrap();
+
+  let head_commit = tmp.repo.head().unwrap().peel_to_commit().unwrap();
+  let tree = head_commit.tree().unwrap();
+  let entry = tree.get_path(std::path::Path::new("tracked.txt")).unwrap();
+  let blob = tmp.repo.find_blob(entry.id()).unwrap();
+  assert_eq!(blob.content(), b"v2");
+  assert!(tree.get_path(std::path::Path::new("untracked.txt")).is_err());
+}
+
+#[test]
+fn test_resolve_base_branch_precedence() {
+  let tmp = init_temp_repo();
+  assert_eq!(resolve_base_branch(&tmp.repo, None), "main");
+  assert_eq!(resolve_base_branch(&tmp.repo, Some("develop")), "develop");
+
+  let mut config = tmp.repo.config().unwrap();
+  config.set_str("commitment.base", "trunk").unwrap();
+  assert_eq!(resolve_base_branch(&tmp.repo, None), "trunk");
+  assert_eq!(resolve_base_branch(&tmp.repo, Some("develop")), "develop");
+}
+
+#[test]
+fn test_find_ticket_from_base_via_commit_summary() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("file.txt"), "v1").unwrap();
+  add_and_commit(&tmp.repo, "Initial", false, None, false, &[], false).unwrap();
+
+  let head_commit = tmp.repo.head().unwrap().peel_to_commit().unwrap();
+  tmp.repo.branch("main", &head_commit, true).unwrap();
+  tmp.repo.branch("improve-logging", &head_commit, true).unwrap();
+  tmp.repo.set_head("refs/heads/improve-logging").unwrap();
+
+  std::fs::write(tmp.dir.join("file.txt"), "v2").unwrap();
+  add_and_commit(&tmp.repo, "ABC-42 Add logging", false, None, false, &[], false).unwrap();
+
+  assert_eq!(find_ticket_from_base(&tmp.repo, "main"), Some("ABC-42".to_string()));
+}
+
+#[test]
+fn test_find_ticket_from_base_via_branch_name() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("file.txt"), "v1").unwrap();
+  add_and_commit(&tmp.repo, "Initial", false, None, false, &[], false).unwrap();
+
+  let head_commit = tmp.repo.head().unwrap().peel_to_commit().unwrap();
+  tmp.repo.branch("main", &head_commit, true).unwrap();
+  tmp.repo.branch("DEF-7-feature", &head_commit, true).unwrap();
+  tmp.repo.set_head("refs/heads/DEF-7-feature").unwrap();
+
+  std::fs::write(tmp.dir.join("file.txt"), "v2").unwrap();
+  add_and_commit(&tmp.repo, "Tidy up the feature", false, None, false, &[], false).unwrap();
+
+  assert_eq!(find_ticket_from_base(&tmp.repo, "main"), Some("DEF-7".to_string()));
+}
+
+#[test]
+fn test_ticket_pattern_from_toml_unescapes_backslashes() {
+  let toml = r#"ticket_pattern = "^(ENG-\\d+)(\\S*)?(?:\\s+(.*))?$""#;
+
+  let pattern = ticket_pattern_from_toml(toml).unwrap();
+  assert_eq!(pattern, r"^(ENG-\d+)(\S*)?(?:\s+(.*))?$");
+
+  let regex = compile_ticket_pattern(&pattern).unwrap();
+  let cap = regex.captures("ENG-42 Fix thing").unwrap();
+  assert_eq!(cap.get(1).unwrap().as_str(), "ENG-42");
+  assert_eq!(cap.get(3).unwrap().as_str(), "Fix thing");
+}
+
+#[test]
+fn test_ticket_pattern_from_toml_unescapes_quotes() {
+  let toml = r#"ticket_pattern = "^(\"ENG-\\d+\")$""#;
+
+  let pattern = ticket_pattern_from_toml(toml).unwrap();
+  assert_eq!(pattern, r#"^("ENG-\d+")$"#);
+}
+
+#[test]
+fn test_amend_with_signing_preserves_original_author() {
+  let tmp = init_temp_repo();
+  let key_path = tmp.dir.join("test_signing_key");
+  let status = std::process::Command::new("ssh-keygen")
+    .args(["-t", "ed25519", "-N", "", "-f"])
+    .arg(&key_path)
+    .args(["-q"])
+    .status()
+    .unwrap();
+  assert!(status.success());
+
+  {
+    let mut config = tmp.repo.config().unwrap();
+    config.set_str("gpg.format", "ssh").unwrap();
+    config.set_str("user.signingkey", key_path.to_str().unwrap()).unwrap();
+  }
+
+  std::fs::write(tmp.dir.join("file.txt"), "v1").unwrap();
+  add_and_commit(&tmp.repo, "First message", false, None, false, &[], false).unwrap();
+  let original_author = tmp.repo.head().unwrap().peel_to_commit().unwrap().author().name().map(str::to_string);
+
+  std::fs::write(tmp.dir.join("file.txt"), "v2").unwrap();
+  add_and_commit(&tmp.repo, "Amended message", false, Some(true), true, &[], false).unwrap();
+
+  let head_commit = tmp.repo.head().unwrap().peel_to_commit().unwrap();
+  assert_eq!(head_commit.summary(), Some("Amended message"));
+  assert_eq!(head_commit.author().name().map(str::to_string), original_author);
+  assert!(head_commit.header_field_bytes("gpgsig").is_ok());
+}
+
+#[test]
+fn test_tracked_only_respects_pathspecs() {
+  let tmp = init_temp_repo();
+  std::fs::write(tmp.dir.join("a.txt"), "a1").unwrap();
+  std::fs::write(tmp.dir.join("b.txt"), "b1").unwrap();
+  add_and_commit(&tmp.repo, "Initial", false, None, false, &[], false).unwrap();
+
+  std::fs::write(tmp.dir.join("a.txt"), "a2").unwrap();
+  std::fs::write(tmp.dir.join("b.txt"), "b2").unwrap();
+
+  add_and_commit(&tmp.repo, "Only a", false, None, false, &["a.txt".to_string()], true).unwrap();
+
+  let head_commit = tmp.repo.head().unwrap().peel_to_commit().unwrap();
+  let tree = head_commit.tree().unwrap();
+
+  let a_entry = tree.get_path(std::path::Path::new("a.txt")).unwrap();
+  assert_eq!(tmp.repo.find_blob(a_entry.id()).unwrap().content(), b"a2");
+
+  let b_entry = tree.get_path(std::path::Path::new("b.txt")).unwrap();
+  assert_eq!(tmp.repo.find_blob(b_entry.id()).unwrap().content(), b"b1");
+}